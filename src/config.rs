@@ -2,10 +2,11 @@ use crate::constants::REPO_NAME;
 use anyhow::{anyhow, Context, Result};
 use home::home_dir;
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tinted_builder::SchemeSystem;
 use url::Url;
 
@@ -16,19 +17,107 @@ pub const BASE16_SHELL_REPO_URL: &str = "https://github.com/tinted-theming/tinte
 pub const BASE16_SHELL_REPO_NAME: &str = "tinted-shell";
 pub const BASE16_SHELL_THEMES_DIR: &str = "scripts";
 pub const BASE16_SHELL_HOOK: &str = ". %f";
+/// System-wide config, read before the user config so the latter can override it.
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/tinty/config.toml";
+/// Environment variable prefix used when resolving config overrides.
+const ENV_PREFIX: &str = "TINTY";
 
 /// Structure for configuration apply items
-#[derive(Deserialize, Debug)]
+#[derive(Debug)]
 pub struct ConfigItem {
     pub name: String,
     pub path: String,
     pub hook: Option<String>,
-    #[serde(rename = "themes-dir")]
     pub themes_dir: String,
-    #[serde(rename = "supported-systems")]
     pub supported_systems: Option<Vec<SchemeSystem>>,
-    #[serde(rename = "theme-file-extension")]
+    /// `supported-systems` values that didn't deserialize into a known [`SchemeSystem`], kept
+    /// around only so `Config::validate` can report them instead of failing the whole parse.
+    pub(crate) unknown_supported_systems: Vec<String>,
     pub theme_file_extension: Option<String>,
+    /// Pin `path` to a git branch. Mutually exclusive with `tag` and `revision`.
+    pub branch: Option<String>,
+    /// Pin `path` to a git tag. Mutually exclusive with `branch` and `revision`.
+    pub tag: Option<String>,
+    /// Pin `path` to a git revision (commit-ish). Mutually exclusive with `branch` and `tag`.
+    pub revision: Option<String>,
+}
+
+/// Shadow of [`ConfigItem`] deserialized with `supported-systems` left as raw strings, so unknown
+/// values can be sorted into [`ConfigItem::unknown_supported_systems`] instead of failing parsing.
+#[derive(Deserialize)]
+struct RawConfigItem {
+    name: String,
+    path: String,
+    hook: Option<String>,
+    #[serde(rename = "themes-dir")]
+    themes_dir: String,
+    #[serde(rename = "supported-systems")]
+    supported_systems: Option<Vec<String>>,
+    #[serde(rename = "theme-file-extension")]
+    theme_file_extension: Option<String>,
+    branch: Option<String>,
+    tag: Option<String>,
+    revision: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for ConfigItem {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = RawConfigItem::deserialize(deserializer)?;
+        let mut supported_systems = None;
+        let mut unknown_supported_systems = Vec::new();
+
+        if let Some(values) = raw.supported_systems {
+            let mut known = Vec::new();
+            for value in values {
+                match parse_scheme_system(&value) {
+                    Some(system) => known.push(system),
+                    None => unknown_supported_systems.push(value),
+                }
+            }
+            supported_systems = Some(known);
+        }
+
+        Ok(ConfigItem {
+            name: raw.name,
+            path: raw.path,
+            hook: raw.hook,
+            themes_dir: raw.themes_dir,
+            supported_systems,
+            unknown_supported_systems,
+            theme_file_extension: raw.theme_file_extension,
+            branch: raw.branch,
+            tag: raw.tag,
+            revision: raw.revision,
+        })
+    }
+}
+
+impl ConfigItem {
+    /// Returns `true` if more than one of `branch`, `tag` and `revision` is set.
+    fn has_conflicting_git_refs(&self) -> bool {
+        [
+            self.branch.is_some(),
+            self.tag.is_some(),
+            self.revision.is_some(),
+        ]
+        .iter()
+        .filter(|is_set| **is_set)
+        .count()
+            > 1
+    }
+
+    /// Returns `true` if a git ref is pinned but `path` is a local directory rather than a
+    /// remote git URL.
+    fn has_git_ref_with_local_path(&self) -> bool {
+        let has_git_ref = self.branch.is_some() || self.tag.is_some() || self.revision.is_some();
+
+        has_git_ref
+            && Url::parse(self.path.as_str()).is_err()
+            && Path::new(self.path.as_str()).is_dir()
+    }
 }
 
 impl fmt::Display for ConfigItem {
@@ -52,6 +141,15 @@ impl fmt::Display for ConfigItem {
         if !hook.is_empty() {
             writeln!(f, "hook = \"{}\"", hook)?;
         }
+        if let Some(branch) = &self.branch {
+            writeln!(f, "branch = \"{}\"", branch)?;
+        }
+        if let Some(tag) = &self.tag {
+            writeln!(f, "tag = \"{}\"", tag)?;
+        }
+        if let Some(revision) = &self.revision {
+            writeln!(f, "revision = \"{}\"", revision)?;
+        }
         writeln!(f, "supported-systems = [{}]", system_text)?;
         write!(f, "themes-dir = \"{}\"", self.themes_dir)
     }
@@ -65,22 +163,407 @@ pub struct Config {
     pub default_scheme: Option<String>,
     pub items: Option<Vec<ConfigItem>>,
     pub hooks: Option<Vec<String>>,
+    pub aliases: Option<HashMap<String, AliasArgs>>,
+}
+
+/// The subcommand argument list a `[aliases]` entry expands to.
+///
+/// Accepts either a whitespace-split string (`aliases.dark = "apply base16-default-dark"`) or a
+/// TOML array (`aliases.dark = ["apply", "base16-default-dark"]`).
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum AliasArgs {
+    Joined(String),
+    List(Vec<String>),
+}
+
+impl AliasArgs {
+    fn to_vec(&self) -> Vec<String> {
+        match self {
+            AliasArgs::Joined(command) => command.split_whitespace().map(String::from).collect(),
+            AliasArgs::List(args) => args.clone(),
+        }
+    }
+}
+
+/// Names of the subcommands built into the CLI, which `[aliases]` entries may not shadow.
+pub const BUILTIN_SUBCOMMANDS: &[&str] = &[
+    "apply",
+    "build",
+    "config",
+    "generate-completion",
+    "info",
+    "init",
+    "install",
+    "list",
+    "uninstall",
+    "update",
+];
+
+/// A single problem found while validating a config, named so the aggregated error can point at
+/// the offending item/alias/field rather than just stopping at the first one found.
+struct ConfigProblem {
+    context: String,
+    message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.message)
+    }
+}
+
+/// Every problem found validating a config, rendered as one numbered list so a user editing a
+/// large config sees everything wrong in one run instead of fixing and re-running repeatedly.
+#[derive(Debug)]
+pub struct ConfigValidationErrors(Vec<String>);
+
+impl fmt::Display for ConfigValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Found {} problem(s) in your {} configuration:",
+            self.0.len(),
+            REPO_NAME
+        )?;
+        for (index, problem) in self.0.iter().enumerate() {
+            writeln!(f, "  {}. {}", index + 1, problem)?;
+        }
+
+        Ok(())
+    }
 }
 
-fn ensure_item_name_is_unique(items: &[ConfigItem]) -> Result<()> {
-    let mut names = HashSet::new();
+impl std::error::Error for ConfigValidationErrors {}
+
+fn validate_items(items: &mut [ConfigItem], problems: &mut Vec<ConfigProblem>) {
+    let mut seen_names = HashSet::new();
+
+    for item in items.iter_mut() {
+        let context = format!("item \"{}\"", item.name);
+
+        if !seen_names.insert(item.name.clone()) {
+            problems.push(ConfigProblem {
+                context: context.clone(),
+                message: "item.name is used by more than one item, it should be unique".into(),
+            });
+        }
+
+        // Expand `path`/`themes-dir` here, rather than bailing out with `?`, so a failure on one
+        // item doesn't prevent the rest of the config from being checked.
+        let path_expanded = match expand_path(item.path.trim()) {
+            Ok(expanded) => {
+                item.path = expanded;
+                true
+            }
+            Err(err) => {
+                problems.push(ConfigProblem {
+                    context: context.clone(),
+                    message: format!("invalid `path`: {}", err),
+                });
+                false
+            }
+        };
+
+        match expand_path(item.themes_dir.trim()) {
+            Ok(expanded) => item.themes_dir = expanded,
+            Err(err) => problems.push(ConfigProblem {
+                context: context.clone(),
+                message: format!("invalid `themes-dir`: {}", err),
+            }),
+        }
+
+        if path_expanded
+            && Url::parse(item.path.as_str()).is_err()
+            && !Path::new(item.path.as_str()).is_dir()
+        {
+            problems.push(ConfigProblem {
+                context: context.clone(),
+                message: format!(
+                    "\"{}\" is not a valid url and is not a path to an existing local directory",
+                    item.path
+                ),
+            });
+        }
+
+        if item.has_conflicting_git_refs() {
+            problems.push(ConfigProblem {
+                context: context.clone(),
+                message: "sets more than one of `branch`, `tag` and `revision`, only one can be used to pin a theme repo".into(),
+            });
+        }
+
+        if path_expanded && item.has_git_ref_with_local_path() {
+            problems.push(ConfigProblem {
+                context: context.clone(),
+                message: "sets `branch`, `tag` or `revision` but `path` is a local directory, git refs only apply to remote git urls".into(),
+            });
+        }
 
-    for item in items.iter() {
-        if !names.insert(&item.name) {
-            return Err(anyhow!("config.toml item.name should be unique values, but \"{}\" is used for more than 1 item.name. Please change this to a unique value.", item.name));
+        for value in &item.unknown_supported_systems {
+            problems.push(ConfigProblem {
+                context: context.clone(),
+                message: format!("unknown supported-systems value \"{}\"", value),
+            });
         }
     }
+}
+
+/// Parse `value` into a [`SchemeSystem`], returning `None` if it isn't a known variant.
+fn parse_scheme_system(value: &str) -> Option<SchemeSystem> {
+    let deserializer: serde::de::value::StrDeserializer<serde::de::value::Error> =
+        serde::de::IntoDeserializer::into_deserializer(value);
 
-    Ok(())
+    SchemeSystem::deserialize(deserializer).ok()
+}
+
+/// Validate an `[aliases]` table: entries may not shadow a built-in subcommand, and may not
+/// expand to another alias, since that would allow recursive or cyclic expansions.
+fn validate_aliases(aliases: &HashMap<String, AliasArgs>, problems: &mut Vec<ConfigProblem>) {
+    for (name, def) in aliases.iter() {
+        let context = || format!("alias \"{}\"", name);
+
+        if BUILTIN_SUBCOMMANDS.contains(&name.as_str()) {
+            problems.push(ConfigProblem {
+                context: context(),
+                message: "shadows a built-in subcommand of the same name, please choose a different alias name".into(),
+            });
+        }
+
+        if let Some(first) = def.to_vec().first() {
+            if aliases.contains_key(first) {
+                problems.push(ConfigProblem {
+                    context: context(),
+                    message: format!(
+                        "expands to another alias \"{}\", aliases cannot reference other aliases",
+                        first
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Expand `~`, `~/...` and `$VAR`/`${VAR}` environment variables in a config path.
+///
+/// A bare `~` resolves to the home dir itself (without doubling the `/` when the home dir is
+/// `/`), and `$VAR`/`${VAR}` segments are resolved against the process environment.
+fn expand_path(path: &str) -> Result<String> {
+    let expanded_home = if path == "~" {
+        match home_dir() {
+            Some(home_dir) => home_dir.display().to_string(),
+            None => return Err(anyhow!("Unable to determine a home directory for \"{}\", please use an absolute path instead", path)),
+        }
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        match home_dir() {
+            Some(home_dir) => {
+                let home_dir = home_dir.display().to_string();
+                if home_dir == "/" {
+                    format!("/{}", rest)
+                } else {
+                    format!("{}/{}", home_dir, rest)
+                }
+            }
+            None => return Err(anyhow!("Unable to determine a home directory for \"{}\", please use an absolute path instead", path)),
+        }
+    } else {
+        path.to_string()
+    };
+
+    expand_env_vars(&expanded_home).map_err(|var_name| {
+        anyhow!(
+            "Couldn't expand \"{}\": environment variable \"${}\" is not set or is missing its closing brace",
+            path,
+            var_name
+        )
+    })
+}
+
+/// Expand `$VAR` and `${VAR}` references in `input` against the process environment.
+///
+/// Returns `Err` with the name of the first variable that can't be resolved.
+fn expand_env_vars(input: &str) -> std::result::Result<String, String> {
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        let braced = chars.peek() == Some(&'{');
+        if braced {
+            chars.next();
+        }
+
+        let var_name: String = chars
+            .clone()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+
+        if var_name.is_empty() {
+            result.push('$');
+            if braced {
+                result.push('{');
+            }
+            continue;
+        }
+
+        // Advance by `char`, not by byte: `var_name.len()` is a byte count and would
+        // over-advance (and silently garble what follows) for non-ASCII variable names.
+        for _ in 0..var_name.chars().count() {
+            chars.next();
+        }
+
+        if braced {
+            if chars.peek() == Some(&'}') {
+                chars.next();
+            } else {
+                return Err(var_name);
+            }
+        }
+
+        match env::var(&var_name) {
+            Ok(value) => result.push_str(&value),
+            Err(_) => return Err(var_name),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Resolve the path to the user config, erroring out if it's ambiguous.
+///
+/// `xdg_path` and `legacy_path` are mutually exclusive: if both point at an existing file we
+/// can't know which one the user means, so we refuse to guess.
+pub fn resolve_user_config_path(xdg_path: &Path, legacy_path: &Path) -> Result<PathBuf> {
+    match (xdg_path.is_file(), legacy_path.is_file()) {
+        (true, true) => Err(anyhow!(
+            "Ambiguous {} configuration: found a config file at both \"{}\" and \"{}\". Remove one of them to resolve the ambiguity.",
+            REPO_NAME,
+            xdg_path.display(),
+            legacy_path.display()
+        )),
+        (true, false) => Ok(xdg_path.to_path_buf()),
+        (false, true) => Ok(legacy_path.to_path_buf()),
+        (false, false) => Ok(xdg_path.to_path_buf()),
+    }
+}
+
+fn no_home_dir_error() -> anyhow::Error {
+    anyhow!(
+        "Unable to determine a home directory to locate the {} config",
+        REPO_NAME
+    )
+}
+
+/// The XDG config location for the user config: `$XDG_CONFIG_HOME/tinty/config.toml`, falling
+/// back to `~/.config/tinty/config.toml` when `XDG_CONFIG_HOME` isn't set.
+fn default_user_config_path() -> Result<PathBuf> {
+    let config_home = match env::var("XDG_CONFIG_HOME") {
+        Ok(value) if !value.is_empty() => PathBuf::from(value),
+        _ => home_dir().ok_or_else(no_home_dir_error)?.join(".config"),
+    };
+
+    Ok(config_home.join(REPO_NAME).join(CONFIG_FILE_NAME))
+}
+
+/// The legacy, pre-XDG user config location: `~/.tinty/config.toml`.
+fn legacy_user_config_path() -> Result<PathBuf> {
+    Ok(home_dir()
+        .ok_or_else(no_home_dir_error)?
+        .join(format!(".{}", REPO_NAME))
+        .join(CONFIG_FILE_NAME))
+}
+
+/// Merge `overlay` on top of `base`: every field set in `overlay` takes precedence, `[[items]]`
+/// entries are merged by `name` with `overlay` winning on conflicts.
+fn merge_configs(base: Config, overlay: Config) -> Config {
+    let items = match (base.items, overlay.items) {
+        (Some(base_items), Some(overlay_items)) => {
+            let overlay_names: HashSet<&String> =
+                overlay_items.iter().map(|item| &item.name).collect();
+            let mut merged: Vec<ConfigItem> = base_items
+                .into_iter()
+                .filter(|item| !overlay_names.contains(&item.name))
+                .collect();
+            merged.extend(overlay_items);
+            Some(merged)
+        }
+        (base_items, None) => base_items,
+        (None, overlay_items) => overlay_items,
+    };
+
+    let aliases = match (base.aliases, overlay.aliases) {
+        (Some(mut base_aliases), Some(overlay_aliases)) => {
+            base_aliases.extend(overlay_aliases);
+            Some(base_aliases)
+        }
+        (base_aliases, None) => base_aliases,
+        (None, overlay_aliases) => overlay_aliases,
+    };
+
+    Config {
+        shell: overlay.shell.or(base.shell),
+        default_scheme: overlay.default_scheme.or(base.default_scheme),
+        items,
+        hooks: overlay.hooks.or(base.hooks),
+        aliases,
+    }
+}
+
+/// Uppercase `name` and replace dashes with underscores so it can be used as an env var segment,
+/// e.g. `my-theme` -> `MY_THEME`.
+fn shouty_snake_case(name: &str) -> String {
+    name.to_uppercase().replace('-', "_")
+}
+
+fn env_override(var_name: &str) -> Option<String> {
+    env::var(var_name).ok().filter(|value| !value.is_empty())
+}
+
+/// Apply `TINTY_*` environment variable overrides on top of an already-merged config.
+///
+/// Scalar fields are overridden by `TINTY_SHELL` and `TINTY_DEFAULT_SCHEME`. Each `[[items]]`
+/// entry's `path` can be overridden by `TINTY_ITEM_<NAME>_PATH`, where `<NAME>` is the item name
+/// uppercased with dashes replaced by underscores.
+fn apply_env_overrides(config: &mut Config) {
+    if let Some(shell) = env_override(&format!("{}_SHELL", ENV_PREFIX)) {
+        config.shell = Some(shell);
+    }
+
+    if let Some(default_scheme) = env_override(&format!("{}_DEFAULT_SCHEME", ENV_PREFIX)) {
+        config.default_scheme = Some(default_scheme);
+    }
+
+    if let Some(ref mut items) = config.items {
+        for item in items.iter_mut() {
+            let item_key = shouty_snake_case(&item.name);
+
+            if let Some(path) = env_override(&format!("{}_ITEM_{}_PATH", ENV_PREFIX, item_key)) {
+                item.path = path;
+            }
+
+            if let Some(themes_dir) =
+                env_override(&format!("{}_ITEM_{}_THEMES_DIR", ENV_PREFIX, item_key))
+            {
+                item.themes_dir = themes_dir;
+            }
+
+            if let Some(hook) = env_override(&format!("{}_ITEM_{}_HOOK", ENV_PREFIX, item_key)) {
+                item.hook = Some(hook);
+            }
+        }
+    }
 }
 
 impl Config {
-    pub fn read(path: &Path) -> Result<Config> {
+    /// Parse `path` into a `Config` without applying defaults, env overrides or validation.
+    ///
+    /// A missing file parses as an empty config so callers can layer several sources on top of
+    /// each other before finalizing.
+    fn parse_raw(path: &Path) -> Result<Config> {
         if path.exists() && !path.is_file() {
             return Err(anyhow!(
                 "The provided config path is a directory and not a file: {}",
@@ -89,13 +572,46 @@ impl Config {
         }
 
         let contents = fs::read_to_string(path).unwrap_or(String::from(""));
-        let mut config: Config = toml::from_str(contents.as_str()).with_context(|| {
+        toml::from_str(contents.as_str()).with_context(|| {
             format!(
                 "Couldn't parse {} configuration file ({:?}). Check if it's syntactically correct",
                 REPO_NAME, path
             )
-        })?;
+        })
+    }
+
+    /// Read `path` as the user config, layering it on top of [`SYSTEM_CONFIG_PATH`] and applying
+    /// `TINTY_*` environment-variable overrides.
+    pub fn read(path: &Path) -> Result<Config> {
+        Config::read_layered(Path::new(SYSTEM_CONFIG_PATH), path)
+    }
+
+    /// Read the user config from its default location, resolved via [`resolve_user_config_path`],
+    /// with the same layering and env overrides [`Config::read`] applies.
+    pub fn read_default() -> Result<Config> {
+        let user_path =
+            resolve_user_config_path(&default_user_config_path()?, &legacy_user_config_path()?)?;
+
+        Config::read(&user_path)
+    }
+
+    /// Resolve a layered config: a system-wide config, a user config and environment-variable
+    /// overrides, merged in that precedence order (user overrides system, env overrides both).
+    pub fn read_layered(system_path: &Path, user_path: &Path) -> Result<Config> {
+        let system_config = if system_path.is_file() {
+            Config::parse_raw(system_path)?
+        } else {
+            Config::parse_raw(Path::new(""))?
+        };
+        let user_config = Config::parse_raw(user_path)?;
+
+        let mut config = merge_configs(system_config, user_config);
+        apply_env_overrides(&mut config);
+
+        Config::finalize(config)
+    }
 
+    fn finalize(mut config: Config) -> Result<Config> {
         // Create default `item`
         let shell = config
             .shell
@@ -107,17 +623,16 @@ impl Config {
             themes_dir: BASE16_SHELL_THEMES_DIR.to_string(),
             hook: Some(BASE16_SHELL_HOOK.to_string()),
             supported_systems: Some(vec![SchemeSystem::Base16]), // DEFAULT_SCHEME_SYSTEM
+            unknown_supported_systems: Vec::new(),
             theme_file_extension: None,
+            branch: None,
+            tag: None,
+            revision: None,
         };
 
         // Add default `item` if no items exist
-        match config.items.as_ref() {
-            Some(items) => {
-                ensure_item_name_is_unique(items)?;
-            }
-            None => {
-                config.items = Some(vec![base16_shell_config_item]);
-            }
+        if config.items.is_none() {
+            config.items = Some(vec![base16_shell_config_item]);
         }
 
         // Set default `system` property for missing systems
@@ -126,41 +641,55 @@ impl Config {
                 if item.supported_systems.is_none() {
                     item.supported_systems = Some(vec![SchemeSystem::default()]);
                 }
+            }
+        }
 
-                // Replace `~/` with absolute home path
-                let trimmed_path = item.path.trim();
-                if trimmed_path.starts_with("~/") {
-                    match home_dir() {
-                        Some(home_dir) => {
-                            item.path = trimmed_path.replacen(
-                                "~/",
-                                format!("{}/", home_dir.display()).as_str(),
-                                1,
-                            );
-                        }
-                        None => {
-                            return Err(anyhow!("Unable to determine a home directory for \"{}\", please use an absolute path instead", item.path));
-                        }
-                    }
-                }
+        config.shell = Some(shell);
 
-                // Return Err if path is not a valid url or an existing directory path
-                if Url::parse(item.path.as_str()).is_err()
-                    && !Path::new(item.path.as_str()).is_dir()
-                {
-                    return Err(anyhow!("One of your config.toml items has an invalid `path` value. \"{}\" is not a valid url and is not a path to an existing local directory", item.path));
-                }
+        Config::validate(&mut config)?;
+
+        Ok(config)
+    }
+
+    /// Validate a defaulted config, collecting every problem instead of stopping at the first
+    /// one so a user editing a large config sees the full list in one run.
+    ///
+    /// This covers duplicate item names, invalid/unresolvable item paths, unknown
+    /// `supported-systems` values, conflicting git-ref fields, a shell missing the `{}`
+    /// placeholder and alias problems. Item `path`/`themes-dir` expansion (`~`, `$VAR`) also
+    /// happens here, so a failure to expand one item doesn't stop the others from being checked.
+    pub fn validate(config: &mut Config) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if let Some(items) = &mut config.items {
+            validate_items(items, &mut problems);
+        }
+
+        if let Some(shell) = &config.shell {
+            if !shell.contains("{}") {
+                problems.push(ConfigProblem {
+                    context: "shell".into(),
+                    message: "does not contain the required command placeholder '{}', check the default file or github for config examples".into(),
+                });
             }
         }
 
-        if !shell.contains("{}") {
-            let msg = "The configured shell does not contain the required command placeholder '{}'. Check the default file or github for config examples.";
-            return Err(anyhow!(msg));
+        if let Some(aliases) = &config.aliases {
+            validate_aliases(aliases, &mut problems);
         }
 
-        config.shell = Some(shell);
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigValidationErrors(problems.iter().map(ToString::to_string).collect()).into())
+        }
+    }
 
-        Ok(config)
+    /// Resolve a user-defined alias into the subcommand argument list it expands to.
+    ///
+    /// Returns `None` if `name` isn't a configured alias.
+    pub fn resolve_alias(&self, name: &str) -> Option<Vec<String>> {
+        self.aliases.as_ref()?.get(name).map(AliasArgs::to_vec)
     }
 }
 
@@ -188,6 +717,412 @@ impl fmt::Display for Config {
             None => {}
         }
 
+        if let Some(aliases) = &self.aliases {
+            writeln!(f)?;
+            writeln!(f, "[aliases]")?;
+            for (name, def) in aliases.iter() {
+                writeln!(f, "{} = \"{}\"", name, def.to_vec().join(" "))?;
+            }
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Guards every test below that mutates process environment variables: `std::env::set_var`
+    /// is process-global, so concurrently-run tests touching the same or overlapping vars can
+    /// otherwise flake.
+    static ENV_TEST_MUTEX: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn empty_config() -> Config {
+        Config {
+            shell: None,
+            default_scheme: None,
+            items: None,
+            hooks: None,
+            aliases: None,
+        }
+    }
+
+    fn item(name: &str, path: &str) -> ConfigItem {
+        ConfigItem {
+            name: name.to_string(),
+            path: path.to_string(),
+            hook: None,
+            themes_dir: "themes".to_string(),
+            supported_systems: None,
+            unknown_supported_systems: Vec::new(),
+            theme_file_extension: None,
+            branch: None,
+            tag: None,
+            revision: None,
+        }
+    }
+
+    #[test]
+    fn has_conflicting_git_refs_true_when_more_than_one_ref_is_set() {
+        let mut config_item = item("theme", "https://example.com/theme.git");
+        config_item.branch = Some("main".to_string());
+        config_item.tag = Some("v1".to_string());
+
+        assert!(config_item.has_conflicting_git_refs());
+    }
+
+    #[test]
+    fn has_conflicting_git_refs_false_with_a_single_ref() {
+        let mut config_item = item("theme", "https://example.com/theme.git");
+        config_item.branch = Some("main".to_string());
+
+        assert!(!config_item.has_conflicting_git_refs());
+    }
+
+    #[test]
+    fn has_git_ref_with_local_path_true_when_path_is_a_local_directory() {
+        let mut config_item = item("theme", ".");
+        config_item.branch = Some("main".to_string());
+
+        assert!(config_item.has_git_ref_with_local_path());
+    }
+
+    #[test]
+    fn has_git_ref_with_local_path_false_for_a_remote_url() {
+        let mut config_item = item("theme", "https://example.com/theme.git");
+        config_item.branch = Some("main".to_string());
+
+        assert!(!config_item.has_git_ref_with_local_path());
+    }
+
+    #[test]
+    fn merge_configs_overrides_base_item_by_name_and_keeps_the_rest() {
+        let base = Config {
+            items: Some(vec![item("a", "base-a"), item("b", "base-b")]),
+            ..empty_config()
+        };
+        let overlay = Config {
+            items: Some(vec![item("a", "overlay-a")]),
+            ..empty_config()
+        };
+
+        let merged = merge_configs(base, overlay);
+        let items = merged.items.unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().any(|i| i.name == "a" && i.path == "overlay-a"));
+        assert!(items.iter().any(|i| i.name == "b" && i.path == "base-b"));
+    }
+
+    #[test]
+    fn apply_env_overrides_sets_item_path_themes_dir_and_hook() {
+        let _guard = ENV_TEST_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        std::env::set_var("TINTY_ITEM_MY_THEME_PATH", "/overridden/path");
+        std::env::set_var("TINTY_ITEM_MY_THEME_THEMES_DIR", "overridden-themes");
+        std::env::set_var("TINTY_ITEM_MY_THEME_HOOK", "overridden hook");
+
+        let mut config = Config {
+            items: Some(vec![item("my-theme", "/original/path")]),
+            ..empty_config()
+        };
+        apply_env_overrides(&mut config);
+
+        let item = &config.items.unwrap()[0];
+        assert_eq!(item.path, "/overridden/path");
+        assert_eq!(item.themes_dir, "overridden-themes");
+        assert_eq!(item.hook.as_deref(), Some("overridden hook"));
+
+        std::env::remove_var("TINTY_ITEM_MY_THEME_PATH");
+        std::env::remove_var("TINTY_ITEM_MY_THEME_THEMES_DIR");
+        std::env::remove_var("TINTY_ITEM_MY_THEME_HOOK");
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tinty-config-test-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn resolve_user_config_path_errors_on_ambiguous_sources() {
+        let xdg_dir = scratch_dir("ambiguous-xdg");
+        let legacy_dir = scratch_dir("ambiguous-legacy");
+        let xdg_path = xdg_dir.join(CONFIG_FILE_NAME);
+        let legacy_path = legacy_dir.join(CONFIG_FILE_NAME);
+        fs::write(&xdg_path, "").unwrap();
+        fs::write(&legacy_path, "").unwrap();
+
+        let err = resolve_user_config_path(&xdg_path, &legacy_path).unwrap_err();
+
+        assert!(err.to_string().contains("Ambiguous"));
+        assert!(err.to_string().contains(&xdg_path.display().to_string()));
+        assert!(err.to_string().contains(&legacy_path.display().to_string()));
+
+        fs::remove_dir_all(&xdg_dir).unwrap();
+        fs::remove_dir_all(&legacy_dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_user_config_path_picks_whichever_single_source_exists() {
+        let xdg_dir = scratch_dir("single-xdg");
+        let legacy_dir = scratch_dir("single-legacy");
+        let xdg_path = xdg_dir.join(CONFIG_FILE_NAME);
+        let legacy_path = legacy_dir.join(CONFIG_FILE_NAME);
+        fs::write(&legacy_path, "").unwrap();
+
+        let resolved = resolve_user_config_path(&xdg_path, &legacy_path).unwrap();
+
+        assert_eq!(resolved, legacy_path);
+
+        fs::remove_dir_all(&xdg_dir).unwrap();
+        fs::remove_dir_all(&legacy_dir).unwrap();
+    }
+
+    #[test]
+    fn read_layered_applies_shell_env_override() {
+        let _guard = ENV_TEST_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        std::env::set_var("TINTY_SHELL", "zsh -c '{}'");
+
+        let config =
+            Config::read_layered(Path::new("/nonexistent"), Path::new("/nonexistent")).unwrap();
+
+        assert_eq!(config.shell.as_deref(), Some("zsh -c '{}'"));
+
+        std::env::remove_var("TINTY_SHELL");
+    }
+
+    #[test]
+    fn expand_env_vars_does_not_swallow_chars_after_a_multibyte_var_name() {
+        let _guard = ENV_TEST_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        std::env::set_var("FÖO", "bar");
+
+        let result = expand_env_vars("$FÖO/baz").unwrap();
+
+        assert_eq!(result, "bar/baz");
+
+        std::env::remove_var("FÖO");
+    }
+
+    #[test]
+    fn expand_env_vars_resolves_braced_and_bare_forms() {
+        let _guard = ENV_TEST_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        std::env::set_var("TINTY_TEST_VAR", "value");
+
+        assert_eq!(
+            expand_env_vars("$TINTY_TEST_VAR/themes").unwrap(),
+            "value/themes"
+        );
+        assert_eq!(
+            expand_env_vars("${TINTY_TEST_VAR}/themes").unwrap(),
+            "value/themes"
+        );
+
+        std::env::remove_var("TINTY_TEST_VAR");
+    }
+
+    #[test]
+    fn expand_path_resolves_bare_tilde_to_the_home_dir() {
+        let _guard = ENV_TEST_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let expanded = expand_path("~").unwrap();
+
+        assert_eq!(expanded, home_dir().unwrap().display().to_string());
+    }
+
+    #[test]
+    fn expand_path_does_not_double_the_slash_when_home_is_root() {
+        let _guard = ENV_TEST_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let previous_home = env::var("HOME").ok();
+        std::env::set_var("HOME", "/");
+
+        let expanded = expand_path("~/foo").unwrap();
+
+        assert_eq!(expanded, "/foo");
+
+        match previous_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+
+    #[test]
+    fn validate_aliases_rejects_a_builtin_subcommand_name() {
+        let mut aliases = HashMap::new();
+        aliases.insert(
+            "apply".to_string(),
+            AliasArgs::Joined("apply base16-default-dark".to_string()),
+        );
+        let mut config = Config {
+            aliases: Some(aliases),
+            ..empty_config()
+        };
+
+        let err = Config::validate(&mut config).unwrap_err();
+
+        assert!(err.to_string().contains("shadows a built-in subcommand"));
+    }
+
+    #[test]
+    fn validate_aliases_rejects_an_alias_referencing_itself() {
+        let mut aliases = HashMap::new();
+        aliases.insert("dark".to_string(), AliasArgs::Joined("dark".to_string()));
+        let mut config = Config {
+            aliases: Some(aliases),
+            ..empty_config()
+        };
+
+        let err = Config::validate(&mut config).unwrap_err();
+
+        assert!(err.to_string().contains("expands to another alias"));
+    }
+
+    #[test]
+    fn validate_aliases_rejects_mutually_referencing_aliases() {
+        let mut aliases = HashMap::new();
+        aliases.insert("a".to_string(), AliasArgs::Joined("b".to_string()));
+        aliases.insert("b".to_string(), AliasArgs::Joined("a".to_string()));
+        let mut config = Config {
+            aliases: Some(aliases),
+            ..empty_config()
+        };
+
+        let err = Config::validate(&mut config).unwrap_err();
+
+        assert!(err.to_string().contains("expands to another alias"));
+    }
+
+    #[test]
+    fn resolve_alias_expands_joined_and_list_forms() {
+        let mut aliases = HashMap::new();
+        aliases.insert(
+            "dark".to_string(),
+            AliasArgs::List(vec!["apply".to_string(), "base16-default-dark".to_string()]),
+        );
+        aliases.insert(
+            "light".to_string(),
+            AliasArgs::Joined("apply base16-default-light".to_string()),
+        );
+        let config = Config {
+            aliases: Some(aliases),
+            ..empty_config()
+        };
+
+        assert_eq!(
+            config.resolve_alias("dark"),
+            Some(vec!["apply".to_string(), "base16-default-dark".to_string()])
+        );
+        assert_eq!(
+            config.resolve_alias("light"),
+            Some(vec![
+                "apply".to_string(),
+                "base16-default-light".to_string()
+            ])
+        );
+        assert_eq!(config.resolve_alias("missing"), None);
+    }
+
+    #[test]
+    fn validate_aggregates_every_problem_in_one_pass() {
+        let items = vec![
+            ConfigItem {
+                name: "dup".to_string(),
+                path: "not a url and not a dir".to_string(),
+                hook: None,
+                themes_dir: "themes".to_string(),
+                supported_systems: Some(Vec::new()),
+                unknown_supported_systems: vec!["not-a-system".to_string()],
+                theme_file_extension: None,
+                branch: Some("main".to_string()),
+                tag: Some("v1".to_string()),
+                revision: None,
+            },
+            ConfigItem {
+                name: "dup".to_string(),
+                path: "https://example.com/theme".to_string(),
+                hook: None,
+                themes_dir: "themes".to_string(),
+                supported_systems: Some(vec![SchemeSystem::Base16]),
+                unknown_supported_systems: Vec::new(),
+                theme_file_extension: None,
+                branch: None,
+                tag: None,
+                revision: None,
+            },
+        ];
+        let mut config = Config {
+            shell: Some("no placeholder".to_string()),
+            items: Some(items),
+            ..empty_config()
+        };
+
+        let err = Config::validate(&mut config).unwrap_err();
+        let message = err.to_string();
+
+        assert!(message.contains("is used by more than one item"));
+        assert!(message.contains("is not a valid url"));
+        assert!(message.contains("more than one of `branch`, `tag` and `revision`"));
+        assert!(message.contains("unknown supported-systems value \"not-a-system\""));
+        assert!(message.contains("does not contain the required command placeholder"));
+    }
+
+    #[test]
+    fn validate_aggregates_an_unresolvable_item_path_alongside_other_problems() {
+        let items = vec![
+            item("dup", "$TINTY_CONFIG_TEST_UNSET_VAR/theme"),
+            item("dup", "https://example.com/theme"),
+        ];
+        let mut config = Config {
+            items: Some(items),
+            ..empty_config()
+        };
+
+        let err = Config::validate(&mut config).unwrap_err();
+        let message = err.to_string();
+
+        assert!(message.contains("is used by more than one item"));
+        assert!(message.contains("invalid `path`"));
+    }
+
+    #[test]
+    fn parse_scheme_system_accepts_known_values_and_rejects_unknown_ones() {
+        assert!(parse_scheme_system("base16").is_some());
+        assert!(parse_scheme_system("not-a-system").is_none());
+    }
+
+    #[test]
+    fn default_user_config_path_uses_xdg_config_home_when_set() {
+        let _guard = ENV_TEST_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        std::env::set_var("XDG_CONFIG_HOME", "/tmp/tinty-xdg-config-home-test");
+
+        let path = default_user_config_path().unwrap();
+
+        assert_eq!(
+            path,
+            PathBuf::from("/tmp/tinty-xdg-config-home-test/tinty/config.toml")
+        );
+
+        std::env::remove_var("XDG_CONFIG_HOME");
+    }
+}